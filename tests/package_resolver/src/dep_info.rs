@@ -0,0 +1,201 @@
+//! Parsing of the `.d` dep-info files emitted by rustc/cargo alongside build
+//! artifacts, used to recover the true set of files a compiled extension
+//! module actually read (including anything pulled in via `include!`,
+//! `include_str!`, build scripts, or non-`.rs` assets that a static source
+//! scan would miss).
+
+use anyhow::{Context, Result};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Parses a Makefile-style dep-info file of the form `target: dep1 dep2 …`,
+/// unescaping backslash-escaped spaces, and returns the listed dependency
+/// paths. Continuation lines (`dep \` at end of line) are joined first.
+pub fn parse_dep_info(dep_info_path: &Path) -> Result<Vec<PathBuf>> {
+    let contents = fs::read_to_string(dep_info_path)
+        .with_context(|| format!("failed to read dep-info file at {:?}", dep_info_path))?;
+    let joined = contents.replace("\\\n", " ");
+
+    let mut deps = Vec::new();
+    for line in joined.lines() {
+        let Some((_target, rest)) = line.split_once(':') else {
+            continue;
+        };
+        for dep in split_unescaped(rest) {
+            deps.push(PathBuf::from(dep));
+        }
+    }
+    Ok(deps)
+}
+
+/// Splits on unescaped whitespace, turning `\ ` into a literal space within a
+/// single path component.
+fn split_unescaped(s: &str) -> Vec<String> {
+    let mut paths = Vec::new();
+    let mut current = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&' ') => {
+                current.push(' ');
+                chars.next();
+            }
+            c if c.is_whitespace() => {
+                if !current.is_empty() {
+                    paths.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        paths.push(current);
+    }
+    paths
+}
+
+/// Finds the `.d` dep-info files under a cargo target directory that were
+/// produced for any of `candidate_names`, matching on the exact sanitized
+/// name used by rustc for object file names (`-` becomes `_`). Cargo names
+/// the dep-info file after the crate's `[lib] name`, which can differ from
+/// the Python `module_full_name` the rest of the resolver deals in, so
+/// callers should pass both.
+pub fn find_dep_info_files(target_dir: &Path, candidate_names: &[&str]) -> Result<Vec<PathBuf>> {
+    let mut candidate_stems = std::collections::HashSet::new();
+    for name in candidate_names {
+        let sanitized_name = name.replace('-', "_");
+        candidate_stems.insert(format!("lib{sanitized_name}"));
+        candidate_stems.insert(sanitized_name);
+    }
+
+    let mut dep_info_files = Vec::new();
+    for profile_dir in ["debug", "release"] {
+        let dir = target_dir.join(profile_dir);
+        if !dir.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&dir)
+            .with_context(|| format!("failed to read target profile dir {:?}", dir))?
+        {
+            let path = entry?.path();
+            let is_match = path.extension().is_some_and(|ext| ext == "d")
+                && path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .is_some_and(|stem| candidate_stems.contains(stem));
+            if is_match {
+                dep_info_files.push(path);
+            }
+        }
+    }
+    Ok(dep_info_files)
+}
+
+/// Joins a dep-info path onto `base_dir` if it's relative (cargo writes dep
+/// paths relative to the directory cargo was invoked from, which is
+/// `base_dir` here) and canonicalizes the result so the stored provenance
+/// has a fixed meaning regardless of the current working directory at
+/// import time. Falls back to the joined, non-canonicalized path if the
+/// file no longer exists.
+fn normalize_dep_path(path: PathBuf, base_dir: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path
+    } else {
+        base_dir.join(path)
+    };
+    absolute.canonicalize().unwrap_or(absolute)
+}
+
+/// Parses every dep-info file found for `candidate_names` under `target_dir`
+/// and returns the union of paths they reference, sorted and deduplicated,
+/// as absolute paths resolved against `base_dir`. Returns `Ok(None)` if no
+/// dep-info files could be found or any of them could not be read, signalling
+/// that callers should fall back to a full source scan.
+pub fn collect_dependency_provenance(
+    target_dir: &Path,
+    candidate_names: &[&str],
+    base_dir: &Path,
+) -> Result<Option<Vec<PathBuf>>> {
+    let dep_info_files = find_dep_info_files(target_dir, candidate_names)?;
+    if dep_info_files.is_empty() {
+        return Ok(None);
+    }
+
+    let mut all_deps = Vec::new();
+    for dep_info_path in &dep_info_files {
+        match parse_dep_info(dep_info_path) {
+            Ok(deps) => all_deps.extend(
+                deps.into_iter()
+                    .map(|dep| normalize_dep_path(dep, base_dir)),
+            ),
+            Err(_) => return Ok(None),
+        }
+    }
+    all_deps.sort();
+    all_deps.dedup();
+    Ok(Some(all_deps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_temp_file(name: &str, contents: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "maturin_import_hook_dep_info_test_{}_{name}",
+            std::process::id()
+        ));
+        let mut file = fs::File::create(&path).unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn split_unescaped_handles_escaped_spaces() {
+        let parts = split_unescaped(r"/a/b\ c.rs /d/e.rs");
+        assert_eq!(parts, vec!["/a/b c.rs".to_owned(), "/d/e.rs".to_owned()]);
+    }
+
+    #[test]
+    fn split_unescaped_collapses_repeated_whitespace() {
+        let parts = split_unescaped("  /a.rs   /b.rs  ");
+        assert_eq!(parts, vec!["/a.rs".to_owned(), "/b.rs".to_owned()]);
+    }
+
+    #[test]
+    fn parse_dep_info_joins_continuation_lines() {
+        let path = write_temp_file(
+            "continuation.d",
+            "target/debug/libfoo.so: src/lib.rs \\\n    src/other.rs\n",
+        );
+        let deps = parse_dep_info(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            deps,
+            vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/other.rs")]
+        );
+    }
+
+    #[test]
+    fn parse_dep_info_skips_lines_without_a_target() {
+        let path = write_temp_file("headerless.d", "src/lib.rs\nsrc/other.rs\n");
+        let deps = parse_dep_info(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(deps.is_empty());
+    }
+
+    #[test]
+    fn parse_dep_info_handles_escaped_space_in_path() {
+        let path = write_temp_file(
+            "escaped.d",
+            r"target/debug/libfoo.so: /home/my\ project/src/lib.rs",
+        );
+        let deps = parse_dep_info(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(deps, vec![PathBuf::from("/home/my project/src/lib.rs")]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Locating and hashing the resolved `Cargo.lock` for a project, so a
+//! dependency bump (`cargo update`, or editing `Cargo.toml` and
+//! regenerating the lockfile) invalidates a cached build even when no
+//! first-party source file changed.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Whether the `Cargo.toml` in `dir` declares `[workspace]`, i.e. `dir` is a
+/// workspace root. Only used to bound `find_cargo_lock`'s upward walk, so a
+/// crude substring check is enough; it never needs to be more than that.
+fn is_workspace_root(dir: &Path) -> bool {
+    std::fs::read_to_string(dir.join("Cargo.toml"))
+        .is_ok_and(|contents| contents.contains("[workspace]"))
+}
+
+/// Walks up from `manifest_dir` looking for a `Cargo.lock`, mirroring how
+/// cargo itself locates the lockfile for a workspace member. The walk is
+/// bounded to the cargo hierarchy `manifest_dir` belongs to: it stops as
+/// soon as it reaches a declared workspace root, and it never ascends past
+/// a directory that has no `Cargo.toml` of its own, so an unrelated
+/// lockfile in some ancestor directory (a parent repo, `$HOME`, etc.) is
+/// never picked up.
+pub fn find_cargo_lock(manifest_dir: &Path) -> Option<PathBuf> {
+    let mut dir = manifest_dir;
+    loop {
+        let candidate = dir.join("Cargo.lock");
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        if is_workspace_root(dir) {
+            return None;
+        }
+        match dir.parent() {
+            Some(parent) if parent.join("Cargo.toml").is_file() => dir = parent,
+            _ => return None,
+        }
+    }
+}
+
+/// FNV-1a, chosen only because it's a few lines of deterministic arithmetic
+/// with no dependency and no cross-version stability concerns. Unlike
+/// `std::hash::Hasher` implementations (`DefaultHasher`/SipHash), whose
+/// output is explicitly documented as unstable across Rust releases, this
+/// hash is a fixed algorithm, so the cache key below doesn't change out
+/// from under an unchanged `Cargo.lock` just because the resolver was
+/// rebuilt with a different toolchain.
+fn fnv1a64(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    let mut hash = OFFSET_BASIS;
+    for &byte in data {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(PRIME);
+    }
+    hash
+}
+
+/// Returns a hex-encoded hash of the `Cargo.lock` belonging to
+/// `manifest_dir`'s workspace, or `None` if no lockfile could be found. This
+/// is a cache key, not a security boundary, so a simple portable hash is
+/// sufficient and keeps this tool free of extra crate dependencies.
+pub fn hash_cargo_lock(manifest_dir: &Path) -> Result<Option<String>> {
+    let Some(lock_path) = find_cargo_lock(manifest_dir) else {
+        return Ok(None);
+    };
+    let contents = std::fs::read(&lock_path)
+        .with_context(|| format!("failed to read Cargo.lock at {:?}", lock_path))?;
+    Ok(Some(format!("{:016x}", fnv1a64(&contents))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!(
+            "maturin_import_hook_lockfile_test_{}_{name}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn find_cargo_lock_walks_up_to_workspace_root() {
+        let root = temp_dir("walk_up");
+        let member_dir = root.join("member");
+        fs::create_dir_all(&member_dir).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"member\"]",
+        )
+        .unwrap();
+        fs::write(root.join("Cargo.lock"), "").unwrap();
+        fs::write(
+            member_dir.join("Cargo.toml"),
+            "[package]\nname = \"member\"",
+        )
+        .unwrap();
+
+        let found = find_cargo_lock(&member_dir);
+
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(found, Some(root.join("Cargo.lock")));
+    }
+
+    #[test]
+    fn find_cargo_lock_returns_none_when_absent() {
+        let root = temp_dir("absent");
+        let member_dir = root.join("member");
+        fs::create_dir_all(&member_dir).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"member\"]",
+        )
+        .unwrap();
+        fs::write(
+            member_dir.join("Cargo.toml"),
+            "[package]\nname = \"member\"",
+        )
+        .unwrap();
+
+        let found = find_cargo_lock(&member_dir);
+
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn find_cargo_lock_does_not_ascend_past_an_unrelated_ancestor() {
+        let unrelated_ancestor = temp_dir("unrelated_ancestor");
+        fs::write(unrelated_ancestor.join("Cargo.lock"), "").unwrap();
+        let project_dir = unrelated_ancestor.join("project");
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(
+            project_dir.join("Cargo.toml"),
+            "[package]\nname = \"project\"",
+        )
+        .unwrap();
+
+        let found = find_cargo_lock(&project_dir);
+
+        fs::remove_dir_all(&unrelated_ancestor).unwrap();
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn hash_cargo_lock_changes_with_contents() {
+        let root = temp_dir("hash");
+        fs::write(root.join("Cargo.lock"), "version 1").unwrap();
+        let first = hash_cargo_lock(&root).unwrap();
+        fs::write(root.join("Cargo.lock"), "version 2").unwrap();
+        let second = hash_cargo_lock(&root).unwrap();
+
+        fs::remove_dir_all(&root).unwrap();
+        assert_ne!(first, second);
+        assert!(first.is_some());
+    }
+}
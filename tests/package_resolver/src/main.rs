@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use maturin::BuildOptions;
 use serde_json::{json, Value};
 use std::{
@@ -7,6 +7,10 @@ use std::{
     process::Command,
 };
 
+mod dep_info;
+mod lockfile;
+mod workspace;
+
 struct TemporaryChdir {
     old_dir: PathBuf,
 }
@@ -27,11 +31,14 @@ impl Drop for TemporaryChdir {
     }
 }
 
-fn resolve_package(project_root: &Path) -> Result<Value> {
+fn resolve_package(project_root: &Path, package: Option<&str>) -> Result<Value> {
     let project_root = project_root.canonicalize()?;
     let _cwd = TemporaryChdir::chdir(&project_root)?;
 
-    let build_options: BuildOptions = Default::default();
+    let mut build_options: BuildOptions = Default::default();
+    if let Some(package) = package {
+        build_options.cargo.packages = vec![package.to_owned()];
+    }
     let build_context = build_options.into_build_context().build()?;
     let extension_module_dir = if build_context.project_layout.python_module.is_some() {
         Some(relative_path(
@@ -47,12 +54,31 @@ fn resolve_package(project_root: &Path) -> Result<Value> {
         None
     };
 
+    // Recover the real read set of the build (including anything pulled in via
+    // `include!`/`include_str!`, build scripts, or non-`.rs` assets) from the
+    // `.d` dep-info files cargo emits, so the import hook can invalidate its
+    // cache on exactly the files that matter instead of scanning source trees.
+    let dependency_provenance = dep_info::collect_dependency_provenance(
+        &build_context.target_dir,
+        &[&build_context.module_name, &build_context.crate_name],
+        &project_root,
+    )?;
+
+    let cargo_lock_hash = lockfile::hash_cargo_lock(
+        build_context
+            .manifest_path
+            .parent()
+            .context("cargo manifest path has no parent directory")?,
+    )?;
+
     Ok(json!({
         "cargo_manifest_path": relative_path(&build_context.manifest_path, &project_root)?,
         "python_dir": relative_path(&build_context.project_layout.python_dir, &project_root)?,
         "python_module": python_module,
         "module_full_name": build_context.module_name,
-        "extension_module_dir": extension_module_dir
+        "extension_module_dir": extension_module_dir,
+        "dependency_provenance": dependency_provenance,
+        "cargo_lock_hash": cargo_lock_hash
     }))
 }
 
@@ -74,14 +100,35 @@ fn resolve_all_packages(test_crates_dir: &Path) -> Result<Value> {
     for path in entries {
         if path.join("pyproject.toml").exists() {
             let project_name = path.file_name().unwrap().to_str().unwrap().to_owned();
-            println!("resolve '{}'", project_name);
-            match resolve_package(&path) {
-                Ok(value) => {
-                    resolved_packages.insert(project_name, value);
+            let buildable_packages = workspace::find_buildable_packages(&path);
+            if buildable_packages.len() <= 1 {
+                // Not a multi-extension workspace: key the entry by the directory
+                // name as before, selecting the sole buildable member explicitly
+                // if there is one and cargo's default package otherwise.
+                println!("resolve '{}'", project_name);
+                match resolve_package(&path, buildable_packages.first().map(String::as_str)) {
+                    Ok(value) => {
+                        resolved_packages.insert(project_name, value);
+                    }
+                    Err(err) => {
+                        println!("resolve failed with: {:?}", err);
+                        resolved_packages.insert(project_name, Value::Null);
+                    }
                 }
-                Err(err) => {
-                    println!("resolve failed with: {:?}", err);
-                    resolved_packages.insert(project_name, Value::Null);
+            } else {
+                for package in buildable_packages {
+                    println!("resolve '{}' package '{}'", project_name, package);
+                    match resolve_package(&path, Some(&package)) {
+                        Ok(value) => {
+                            let module_full_name =
+                                value["module_full_name"].as_str().unwrap().to_owned();
+                            resolved_packages.insert(module_full_name, value);
+                        }
+                        Err(err) => {
+                            println!("resolve failed with: {:?}", err);
+                            resolved_packages.insert(package, Value::Null);
+                        }
+                    }
                 }
             }
         }
@@ -0,0 +1,80 @@
+//! Discovery of buildable cargo workspace members: packages that declare a
+//! `[lib] crate-type = ["cdylib"]`, i.e. the ones maturin can turn into a
+//! Python extension module. `resolve_all_packages` assumed one default
+//! package per `pyproject.toml` directory; this lets it resolve every
+//! extension in a workspace instead of just the one cargo would pick by
+//! default.
+
+use serde_json::Value;
+use std::{path::Path, process::Command};
+
+/// Returns the names of every workspace member under `project_root` whose
+/// `[lib]` target declares `crate-type = ["cdylib"]`. Returns an empty list
+/// (rather than erroring) if cargo metadata can't be read or parsed, so
+/// callers fall back to the single-default-package behavior instead of
+/// aborting resolution of every other crate over one bad manifest.
+///
+/// Shells out to `cargo metadata` rather than depending on the
+/// `cargo_metadata` crate directly, since this tool only reads the JSON with
+/// `serde_json`, which it already depends on.
+pub fn find_buildable_packages(project_root: &Path) -> Vec<String> {
+    let manifest_path = project_root.join("Cargo.toml");
+    if !manifest_path.is_file() {
+        return Vec::new();
+    }
+
+    let output = match Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        Ok(output) => {
+            println!(
+                "cargo metadata failed for {:?}: {}",
+                manifest_path,
+                String::from_utf8_lossy(&output.stderr)
+            );
+            return Vec::new();
+        }
+        Err(err) => {
+            println!(
+                "failed to run cargo metadata for {:?}: {}",
+                manifest_path, err
+            );
+            return Vec::new();
+        }
+    };
+
+    let Ok(metadata) = serde_json::from_slice::<Value>(&output.stdout) else {
+        println!(
+            "failed to parse cargo metadata output for {:?}",
+            manifest_path
+        );
+        return Vec::new();
+    };
+
+    let mut buildable = Vec::new();
+    let packages = metadata["packages"].as_array().cloned().unwrap_or_default();
+    for package in packages {
+        let is_cdylib = package["targets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .any(|target| {
+                target["crate_types"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .any(|ty| ty == "cdylib")
+            });
+        if is_cdylib {
+            if let Some(name) = package["name"].as_str() {
+                buildable.push(name.to_owned());
+            }
+        }
+    }
+    buildable.sort();
+    buildable
+}